@@ -1,9 +1,12 @@
 use neon::prelude::*;
 use neon::types::JsPromise;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use font_kit::source::SystemSource;
 use font_kit::family_name::FamilyName;
-use font_kit::properties::Properties;
+use font_kit::properties::{Properties, Stretch, Style, Weight};
+use font_kit::font::Font;
 use font_kit::handle::Handle;
 use log::debug;
 
@@ -15,23 +18,66 @@ mod macos;
 #[cfg(target_os = "windows")]
 mod windows;
 
+// Dependency-free OpenType table reader, used to surface feature tags and
+// variable-font axes without pulling in a full font-parsing crate
+mod opentype;
+
+// Process-global font cache keyed by family, modeled on Servo's font-cache
+// thread: walking the whole system collection is slow on machines with
+// thousands of fonts, so we do it once and answer subsequent lookups out
+// of memory instead of touching the OS again.
+struct FontCache {
+    by_family: HashMap<String, Vec<FontInfo>>,
+    populated: bool,
+}
+
+static FONT_CACHE: Lazy<Mutex<FontCache>> = Lazy::new(|| {
+    Mutex::new(FontCache {
+        by_family: HashMap::new(),
+        populated: false,
+    })
+});
+
+// Walks the system font collection and fills the cache, unless it's
+// already been populated. Safe to call from any thread; callers only pay
+// the enumeration cost once.
+fn ensure_cache_populated() {
+    let mut cache = FONT_CACHE.lock().unwrap();
+    if cache.populated {
+        return;
+    }
+
+    for font in get_system_fonts() {
+        cache.by_family.entry(font.name.clone()).or_default().push(font);
+    }
+    cache.populated = true;
+}
+
 // This function will return a promise that resolves to an array of font information
 fn get_fonts(mut cx: FunctionContext) -> JsResult<JsPromise> {
     // Create a promise with associated deferred object to resolve later
     let (deferred, promise) = cx.promise();
-    
+
     // We need to spawn a new thread to do the font detection work
     // so we don't block the Node.js event loop
     let channel = cx.channel();
-    
+
     // Safety: We're capturing the deferred object to resolve the promise later
     let deferred = Arc::new(Mutex::new(Some(deferred)));
-    
+
     // Spawn a new thread to do the font detection work
     std::thread::spawn(move || {
-        // Use font-kit to get system fonts
-        let fonts = get_system_fonts();
-        
+        // Serve from the cache, populating it on first use
+        ensure_cache_populated();
+        let fonts: Vec<FontInfo> = FONT_CACHE
+            .lock()
+            .unwrap()
+            .by_family
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+
         // Send the fonts back to the main thread to resolve the promise
         let deferred = Arc::clone(&deferred);
         channel.send(move |mut cx| {
@@ -45,10 +91,20 @@ fn get_fonts(mut cx: FunctionContext) -> JsResult<JsPromise> {
                 let js_obj = cx.empty_object();
                 let js_name = cx.string(&font.name);
                 let js_path = cx.string(&font.path);
-                
+                let js_weight = cx.number(font.weight);
+                let js_italic = cx.boolean(font.italic);
+                let js_stretch = cx.number(font.stretch);
+                let js_features = build_features_array(&mut cx, &font.features)?;
+                let js_axes = build_axes_array(&mut cx, &font.axes)?;
+
                 js_obj.set(&mut cx, "name", js_name)?;
                 js_obj.set(&mut cx, "path", js_path)?;
-                
+                js_obj.set(&mut cx, "weight", js_weight)?;
+                js_obj.set(&mut cx, "italic", js_italic)?;
+                js_obj.set(&mut cx, "stretch", js_stretch)?;
+                js_obj.set(&mut cx, "features", js_features)?;
+                js_obj.set(&mut cx, "axes", js_axes)?;
+
                 js_array.set(&mut cx, i as u32, js_obj)?;
             }
             
@@ -63,46 +119,116 @@ fn get_fonts(mut cx: FunctionContext) -> JsResult<JsPromise> {
     Ok(promise)
 }
 
+// A single variable-font axis, e.g. the "wght" axis on a variable weight font
+#[derive(Debug, Clone)]
+pub struct FontAxis {
+    pub tag: String,
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+}
+
+// Builds a JS array of feature tag strings
+fn build_features_array<'a>(
+    cx: &mut impl Context<'a>,
+    features: &[String],
+) -> JsResult<'a, JsArray> {
+    let js_array = cx.empty_array();
+    for (i, tag) in features.iter().enumerate() {
+        let js_tag = cx.string(tag);
+        js_array.set(cx, i as u32, js_tag)?;
+    }
+    Ok(js_array)
+}
+
+// Builds a JS array of { tag, min, max, default } variation axis objects
+fn build_axes_array<'a>(cx: &mut impl Context<'a>, axes: &[FontAxis]) -> JsResult<'a, JsArray> {
+    let js_array = cx.empty_array();
+    for (i, axis) in axes.iter().enumerate() {
+        let js_axis = cx.empty_object();
+        let js_tag = cx.string(&axis.tag);
+        let js_min = cx.number(axis.min);
+        let js_max = cx.number(axis.max);
+        let js_default = cx.number(axis.default);
+
+        js_axis.set(cx, "tag", js_tag)?;
+        js_axis.set(cx, "min", js_min)?;
+        js_axis.set(cx, "max", js_max)?;
+        js_axis.set(cx, "default", js_default)?;
+
+        js_array.set(cx, i as u32, js_axis)?;
+    }
+    Ok(js_array)
+}
+
 // Font information structure
 #[derive(Debug, Clone)]
 pub struct FontInfo {
     pub name: String,
     pub path: String,
+    pub weight: u16,
+    pub italic: bool,
+    pub stretch: f32,
+    pub features: Vec<String>,
+    pub axes: Vec<FontAxis>,
+}
+
+// Turns a font-kit Handle into our own FontInfo, reading weight/style/stretch
+// off the loaded Font, and OpenType features/variation axes out of the raw
+// font bytes, so every caller (family enumeration, matchFont) reports faces
+// the same way.
+fn font_info_from_handle(handle: Handle, family_name: &str) -> FontInfo {
+    let properties = Font::from_handle(&handle)
+        .map(|font| font.properties())
+        .unwrap_or_default();
+    let weight = properties.weight.0 as u16;
+    let italic = matches!(properties.style, Style::Italic | Style::Oblique);
+    let stretch = properties.stretch.0;
+
+    let (path, data, face_index) = match &handle {
+        Handle::Path { path, font_index } => {
+            let path_str = path.to_string_lossy().to_string();
+            (path_str, std::fs::read(path).ok(), *font_index)
+        }
+        // Memory fonts don't have a path, but we still report them with an empty path
+        Handle::Memory { bytes, font_index } => {
+            (String::new(), Some((**bytes).clone()), *font_index)
+        }
+    };
+
+    let (features, axes) = match &data {
+        Some(bytes) => (
+            opentype::read_features(bytes, face_index),
+            opentype::read_variation_axes(bytes, face_index),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    FontInfo {
+        name: family_name.to_string(),
+        path,
+        weight,
+        italic,
+        stretch,
+        features,
+        axes,
+    }
 }
 
 // Use font-kit to get system fonts
 fn get_system_fonts() -> Vec<FontInfo> {
     let mut fonts = Vec::new();
     let source = SystemSource::new();
-    
-    // Function to process a font handle
-    let mut process_handle = |handle: Handle, family_name: &str| {
-        match handle {
-            Handle::Path { path, .. } => {
-                let path_str = path.to_string_lossy().to_string();
-                fonts.push(FontInfo {
-                    name: family_name.to_string(),
-                    path: path_str,
-                });
-            },
-            Handle::Memory { .. } => {
-                // Memory fonts don't have a path, but we still report them with an empty path
-                fonts.push(FontInfo {
-                    name: family_name.to_string(),
-                    path: String::new(),
-                });
-            },
-        }
-    };
-    
+
     // Get all font families available on the system
     if let Ok(font_families) = source.all_families() {
         for family_name in font_families {
-            // Try to get a specific font from this family with default properties
-            let default_properties = Properties::new();
-            
-            if let Ok(handle) = source.select_best_match(&[FamilyName::Title(family_name.clone())], &default_properties) {
-                process_handle(handle, &family_name);
+            // Enumerate every face in the family (regular, bold, italic,
+            // condensed, ...) instead of collapsing it to one representative.
+            if let Ok(family) = source.select_family_by_name(&family_name) {
+                for handle in family.fonts() {
+                    fonts.push(font_info_from_handle(handle.clone(), &family_name));
+                }
             }
         }
     } else {
@@ -137,9 +263,311 @@ fn get_system_fonts() -> Vec<FontInfo> {
     fonts
 }
 
+// Requested match criteria for matchFont, mirroring CSS font-matching inputs
+#[derive(Debug, Clone, Copy)]
+pub struct MatchRequest {
+    pub weight: u16,
+    pub italic: bool,
+    pub stretch: f32,
+}
+
+impl Default for MatchRequest {
+    fn default() -> Self {
+        MatchRequest {
+            weight: 400,
+            italic: false,
+            stretch: 1.0,
+        }
+    }
+}
+
+// Picks the nearest weight out of a set of candidates using the classic CSS
+// font-matching tie-break: below 400, search downward then upward; at or
+// above 500, search upward then downward.
+pub fn nearest_weight(candidates: &[u16], target: u16) -> Option<u16> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    if candidates.contains(&target) {
+        return Some(target);
+    }
+
+    let (first, second): (Box<dyn Fn(u16) -> bool>, Box<dyn Fn(u16) -> bool>) = if target < 400 {
+        (Box::new(move |w| w < target), Box::new(move |w| w > target))
+    } else {
+        (Box::new(move |w| w > target), Box::new(move |w| w < target))
+    };
+
+    let closest = |pred: &dyn Fn(u16) -> bool| -> Option<u16> {
+        candidates
+            .iter()
+            .copied()
+            .filter(|w| pred(*w))
+            .min_by_key(|w| (*w as i32 - target as i32).abs())
+    };
+
+    closest(&*first).or_else(|| closest(&*second))
+}
+
+// Picks the nearest stretch factor out of a set of candidates: plain
+// closest-match, since font-stretch has no directional tie-break in the CSS
+// font-matching algorithm the way weight does.
+fn nearest_stretch(candidates: &[f32], target: f32) -> Option<f32> {
+    candidates
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - target).abs().partial_cmp(&(b - target).abs()).unwrap())
+}
+
+// Picks the best face out of an already-known set of candidates: prefer
+// the requested slant, falling back to whatever's available, then narrow to
+// the nearest stretch, then resolve weight with the CSS nearest-weight
+// tie-break.
+pub(crate) fn select_best_face(candidates: &[FontInfo], request: MatchRequest) -> Option<FontInfo> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let same_slant: Vec<&FontInfo> = candidates
+        .iter()
+        .filter(|font| font.italic == request.italic)
+        .collect();
+    let pool = if same_slant.is_empty() {
+        candidates.iter().collect::<Vec<_>>()
+    } else {
+        same_slant
+    };
+
+    let stretches: Vec<f32> = pool.iter().map(|font| font.stretch).collect();
+    let target_stretch = nearest_stretch(&stretches, request.stretch)?;
+    let pool: Vec<&FontInfo> = pool
+        .into_iter()
+        .filter(|font| (font.stretch - target_stretch).abs() < f32::EPSILON)
+        .collect();
+
+    let weights: Vec<u16> = pool.iter().map(|font| font.weight).collect();
+    let target_weight = nearest_weight(&weights, request.weight)?;
+
+    pool.into_iter().find(|font| font.weight == target_weight).cloned()
+}
+
+// Find the font that best matches a requested family and style. Serves
+// the request from the font cache, populating it on first use, so repeat
+// queries don't re-walk the OS font collection.
+fn find_matching_font(family: &str, request: MatchRequest) -> Option<FontInfo> {
+    ensure_cache_populated();
+
+    if let Some(candidates) = FONT_CACHE.lock().unwrap().by_family.get(family) {
+        if let Some(font) = select_best_face(candidates, request) {
+            return Some(font);
+        }
+    }
+
+    // Fall through to a live OS lookup, e.g. for a font installed after
+    // the cache was populated and not yet picked up by refreshFonts().
+    find_matching_font_live(family, request)
+}
+
+// Tries font-kit's own selection first (it already implements nearest-style
+// matching), then falls through to the native platform matcher.
+fn find_matching_font_live(family: &str, request: MatchRequest) -> Option<FontInfo> {
+    let source = SystemSource::new();
+    let style = if request.italic { Style::Italic } else { Style::Normal };
+    let properties = Properties {
+        weight: Weight(request.weight as f32),
+        style,
+        stretch: Stretch(request.stretch),
+    };
+
+    if let Ok(handle) =
+        source.select_best_match(&[FamilyName::Title(family.to_string())], &properties)
+    {
+        return Some(font_info_from_handle(handle, family));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows::match_directwrite_font(family, request);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        return macos::match_core_text_font(family, request);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        return linux::match_fontconfig_font(family, request);
+    }
+
+    #[allow(unreachable_code)]
+    None
+}
+
+// Exposes matchFont(family, { weight, italic, stretch }) to JS, resolving to
+// the best-matching face's info or null if nothing matches.
+fn match_font(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let family = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    let mut request = MatchRequest::default();
+    if let Ok(options) = cx.argument::<JsObject>(1) {
+        if let Ok(weight) = options.get::<JsNumber, _, _>(&mut cx, "weight") {
+            request.weight = weight.value(&mut cx) as u16;
+        }
+        if let Ok(italic) = options.get::<JsBoolean, _, _>(&mut cx, "italic") {
+            request.italic = italic.value(&mut cx);
+        }
+        if let Ok(stretch) = options.get::<JsNumber, _, _>(&mut cx, "stretch") {
+            request.stretch = stretch.value(&mut cx) as f32;
+        }
+    }
+
+    let (deferred, promise) = cx.promise();
+    let channel = cx.channel();
+    let deferred = Arc::new(Mutex::new(Some(deferred)));
+
+    std::thread::spawn(move || {
+        let result = find_matching_font(&family, request);
+
+        let deferred = Arc::clone(&deferred);
+        channel.send(move |mut cx| {
+            let mut deferred_guard = deferred.lock().unwrap();
+            let deferred = deferred_guard.take().unwrap();
+
+            match result {
+                Some(font) => {
+                    let js_obj = cx.empty_object();
+                    let js_name = cx.string(&font.name);
+                    let js_path = cx.string(&font.path);
+                    let js_weight = cx.number(font.weight);
+                    let js_italic = cx.boolean(font.italic);
+                    let js_stretch = cx.number(font.stretch);
+                    let js_features = build_features_array(&mut cx, &font.features)?;
+                    let js_axes = build_axes_array(&mut cx, &font.axes)?;
+
+                    js_obj.set(&mut cx, "name", js_name)?;
+                    js_obj.set(&mut cx, "path", js_path)?;
+                    js_obj.set(&mut cx, "weight", js_weight)?;
+                    js_obj.set(&mut cx, "italic", js_italic)?;
+                    js_obj.set(&mut cx, "stretch", js_stretch)?;
+                    js_obj.set(&mut cx, "features", js_features)?;
+                    js_obj.set(&mut cx, "axes", js_axes)?;
+
+                    deferred.resolve(&mut cx, js_obj);
+                }
+                None => {
+                    let js_null = cx.null();
+                    deferred.resolve(&mut cx, js_null);
+                }
+            }
+
+            Ok(())
+        });
+    });
+
+    Ok(promise)
+}
+
+// Pulls the raw bytes and face index out of a font-kit Handle. Memory
+// handles already carry their bytes; Path handles are read from disk.
+fn font_data_from_handle(handle: Handle) -> Option<(Vec<u8>, u32)> {
+    match handle {
+        Handle::Memory { bytes, font_index } => Some(((*bytes).clone(), font_index)),
+        Handle::Path { path, font_index } => {
+            let bytes = std::fs::read(&path).ok()?;
+            Some((bytes, font_index))
+        }
+    }
+}
+
+// Resolves font data for either a file path or a family name. Paths are
+// read straight from disk; family names go through font-kit's selection
+// and, on Windows, fall through to a native DWrite stream read for fonts
+// that have no local file (TrueType collections, streamed fonts).
+fn resolve_font_data(input: &str) -> Option<(Vec<u8>, u32)> {
+    let path = std::path::Path::new(input);
+    if path.is_file() {
+        let bytes = std::fs::read(path).ok()?;
+        return Some((bytes, 0));
+    }
+
+    let source = SystemSource::new();
+    if let Ok(handle) =
+        source.select_best_match(&[FamilyName::Title(input.to_string())], &Properties::new())
+    {
+        if let Some(data) = font_data_from_handle(handle) {
+            return Some(data);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows::font_data_for_family(input);
+    }
+
+    #[allow(unreachable_code)]
+    None
+}
+
+// Exposes getFontData(path_or_family), resolving to { data: Buffer, faceIndex }
+// or null if nothing could be found.
+fn get_font_data(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let input = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    let (deferred, promise) = cx.promise();
+    let channel = cx.channel();
+    let deferred = Arc::new(Mutex::new(Some(deferred)));
+
+    std::thread::spawn(move || {
+        let result = resolve_font_data(&input);
+
+        let deferred = Arc::clone(&deferred);
+        channel.send(move |mut cx| {
+            let mut deferred_guard = deferred.lock().unwrap();
+            let deferred = deferred_guard.take().unwrap();
+
+            match result {
+                Some((bytes, face_index)) => {
+                    let mut buffer = cx.buffer(bytes.len())?;
+                    buffer.as_mut_slice(&mut cx).copy_from_slice(&bytes);
+
+                    let js_obj = cx.empty_object();
+                    let js_face_index = cx.number(face_index);
+                    js_obj.set(&mut cx, "data", buffer)?;
+                    js_obj.set(&mut cx, "faceIndex", js_face_index)?;
+
+                    deferred.resolve(&mut cx, js_obj);
+                }
+                None => {
+                    let js_null = cx.null();
+                    deferred.resolve(&mut cx, js_null);
+                }
+            }
+
+            Ok(())
+        });
+    });
+
+    Ok(promise)
+}
+
+// Exposes refreshFonts(), clearing the font cache so the next getFonts/
+// matchFont call picks up any fonts installed since it was last populated.
+fn refresh_fonts(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let mut cache = FONT_CACHE.lock().unwrap();
+    cache.by_family.clear();
+    cache.populated = false;
+
+    Ok(cx.undefined())
+}
+
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("getFonts", get_fonts)?;
+    cx.export_function("matchFont", match_font)?;
+    cx.export_function("getFontData", get_font_data)?;
+    cx.export_function("refreshFonts", refresh_fonts)?;
     Ok(())
 }
 
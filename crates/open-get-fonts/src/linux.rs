@@ -1,44 +1,141 @@
-use super::FontInfo;
+use super::{opentype, FontInfo, MatchRequest};
 use std::path::PathBuf;
 use std::ffi::CString;
 use fontconfig::{Fontconfig, Pattern, ObjectSet};
 
+// Reads OpenType feature tags and variation axes straight out of the font
+// file fontconfig pointed us at, since fontconfig itself doesn't surface
+// these. Fonts fontconfig can't read back off disk just report neither.
+fn features_and_axes(path: &str, face_index: u32) -> (Vec<String>, Vec<super::FontAxis>) {
+    match std::fs::read(path) {
+        Ok(bytes) => (
+            opentype::read_features(&bytes, face_index),
+            opentype::read_variation_axes(&bytes, face_index),
+        ),
+        Err(_) => (Vec::new(), Vec::new()),
+    }
+}
+
+// fontconfig reports slant/width on their own integer scales (FC_SLANT_*,
+// FC_WIDTH_*). Map them onto the CSS-style scales the rest of the crate
+// uses: weight goes through fc_weight_to_css below; width maps onto
+// font-kit's 0.5-2.0 stretch factor via fontconfig's documented FC_WIDTH_*
+// constants (100 == normal).
+fn stretch_from_fc_width(width: i32) -> f32 {
+    (width as f32 / 100.0).max(0.5).min(2.0)
+}
+
+// fontconfig's FC_WEIGHT is its own legacy 0-215 scale (FC_WEIGHT_REGULAR =
+// 80, FC_WEIGHT_BOLD = 200, FC_WEIGHT_BLACK = 210, ...), not the CSS/OpenType
+// 100-900 scale the rest of the crate (and Windows/macOS) report weight on.
+// These mirror fontconfig's own FcWeightFromOpenType/FcWeightToOpenType
+// breakpoint tables so a given family reports the same weight on every
+// platform.
+const FC_WEIGHT_BREAKPOINTS: &[(f64, f64)] = &[
+    (0.0, 100.0),
+    (40.0, 200.0),
+    (50.0, 300.0),
+    (55.0, 350.0),
+    (75.0, 380.0),
+    (80.0, 400.0),
+    (100.0, 500.0),
+    (180.0, 600.0),
+    (200.0, 700.0),
+    (205.0, 800.0),
+    (210.0, 900.0),
+    (215.0, 1000.0),
+];
+
+fn interpolate(points: &[(f64, f64)], x: f64) -> f64 {
+    if x <= points[0].0 {
+        return points[0].1;
+    }
+    if x >= points[points.len() - 1].0 {
+        return points[points.len() - 1].1;
+    }
+    for pair in points.windows(2) {
+        let (x0, y0) = pair[0];
+        let (x1, y1) = pair[1];
+        if x >= x0 && x <= x1 {
+            return y0 + (x - x0) / (x1 - x0) * (y1 - y0);
+        }
+    }
+    points[points.len() - 1].1
+}
+
+fn fc_weight_to_css(fc_weight: i32) -> u16 {
+    interpolate(FC_WEIGHT_BREAKPOINTS, fc_weight as f64) as u16
+}
+
+fn css_weight_to_fc(css_weight: u16) -> i32 {
+    let reversed: Vec<(f64, f64)> = FC_WEIGHT_BREAKPOINTS
+        .iter()
+        .map(|&(fc, css)| (css, fc))
+        .collect();
+    interpolate(&reversed, css_weight as f64) as i32
+}
+
 pub fn get_fontconfig_fonts() -> Option<Vec<FontInfo>> {
     // Initialize fontconfig
     let fc = Fontconfig::new().or(None)?;
     let mut fonts = Vec::new();
-    
+
     // Create a pattern to match all fonts
     let pattern = Pattern::new(&fc);
-    
+
     // Create an object set for the properties we want to extract
     let mut object_set = ObjectSet::new(&fc);
-    
-    // Add the family and file properties to retrieve
+
+    // Add the family, file, weight, slant and width properties to retrieve
     // Convert the constants to CString objects that can be passed to add()
     let family_cstr = CString::new(fontconfig::FC_FAMILY.to_bytes()).ok()?;
     let file_cstr = CString::new(fontconfig::FC_FILE.to_bytes()).ok()?;
-    
+    let weight_cstr = CString::new(fontconfig::FC_WEIGHT.to_bytes()).ok()?;
+    let slant_cstr = CString::new(fontconfig::FC_SLANT.to_bytes()).ok()?;
+    let width_cstr = CString::new(fontconfig::FC_WIDTH.to_bytes()).ok()?;
+    let index_cstr = CString::new(fontconfig::FC_INDEX.to_bytes()).ok()?;
+
     object_set.add(&family_cstr);
     object_set.add(&file_cstr);
-    
+    object_set.add(&weight_cstr);
+    object_set.add(&slant_cstr);
+    object_set.add(&width_cstr);
+    object_set.add(&index_cstr);
+
     // Use the global list_fonts function from fontconfig
     let font_set = fontconfig::list_fonts(&pattern, Some(&object_set));
-    
+
     // Iterate through patterns in the font set
     for pattern in font_set.iter() {
         // Try to get the family name
         if let Some(family) = pattern.get_string(&family_cstr) {
             // Try to get the file path
             if let Some(path) = pattern.get_string(&file_cstr) {
+                let weight = pattern
+                    .get_int(&weight_cstr)
+                    .map(fc_weight_to_css)
+                    .unwrap_or(400);
+                let italic = pattern.get_int(&slant_cstr).unwrap_or(0) > 0;
+                let stretch = pattern
+                    .get_int(&width_cstr)
+                    .map(stretch_from_fc_width)
+                    .unwrap_or(1.0);
+                let face_index = pattern.get_int(&index_cstr).unwrap_or(0) as u32;
+                let (features, axes) = features_and_axes(path, face_index);
+
                 fonts.push(FontInfo {
                     name: family.to_string(),
                     path: path.to_string(),
+                    weight,
+                    italic,
+                    stretch,
+                    features,
+                    axes,
                 });
             }
         }
     }
-    
+
     // If we didn't find any fonts, return some common ones that are likely to exist
     if fonts.is_empty() {
         for path in &[
@@ -48,13 +145,68 @@ pub fn get_fontconfig_fonts() -> Option<Vec<FontInfo>> {
         ] {
             let path_buf = PathBuf::from(path);
             if path_buf.exists() {
+                let (features, axes) = features_and_axes(path, 0);
                 fonts.push(FontInfo {
                     name: path_buf.file_stem().unwrap_or_default().to_string_lossy().to_string(),
                     path: path.to_string(),
+                    weight: 400,
+                    italic: false,
+                    stretch: 1.0,
+                    features,
+                    axes,
                 });
             }
         }
     }
-    
+
     Some(fonts)
-} 
\ No newline at end of file
+}
+
+// Native fallback for matchFont: build a pattern for the requested family,
+// weight, slant and width and let fontconfig's own FcFontMatch resolve the
+// closest installed face.
+pub fn match_fontconfig_font(family: &str, request: MatchRequest) -> Option<FontInfo> {
+    let fc = Fontconfig::new().or(None)?;
+
+    let family_cstr = CString::new(fontconfig::FC_FAMILY.to_bytes()).ok()?;
+    let file_cstr = CString::new(fontconfig::FC_FILE.to_bytes()).ok()?;
+    let weight_cstr = CString::new(fontconfig::FC_WEIGHT.to_bytes()).ok()?;
+    let slant_cstr = CString::new(fontconfig::FC_SLANT.to_bytes()).ok()?;
+    let width_cstr = CString::new(fontconfig::FC_WIDTH.to_bytes()).ok()?;
+    let index_cstr = CString::new(fontconfig::FC_INDEX.to_bytes()).ok()?;
+
+    let mut pattern = Pattern::new(&fc);
+    pattern.add_string(&family_cstr, family);
+    pattern.add_integer(&weight_cstr, css_weight_to_fc(request.weight));
+    pattern.add_integer(&slant_cstr, if request.italic { 100 } else { 0 });
+    pattern.add_integer(&width_cstr, (request.stretch * 100.0) as i32);
+
+    pattern.default_substitute();
+    pattern.config_substitute(fontconfig::MatchKind::Pattern);
+
+    let matched = pattern.font_match(&fc)?;
+
+    let name = matched.get_string(&family_cstr)?.to_string();
+    let path = matched.get_string(&file_cstr)?.to_string();
+    let weight = matched
+        .get_int(&weight_cstr)
+        .map(fc_weight_to_css)
+        .unwrap_or(400);
+    let italic = matched.get_int(&slant_cstr).unwrap_or(0) > 0;
+    let stretch = matched
+        .get_int(&width_cstr)
+        .map(stretch_from_fc_width)
+        .unwrap_or(1.0);
+    let face_index = matched.get_int(&index_cstr).unwrap_or(0) as u32;
+    let (features, axes) = features_and_axes(&path, face_index);
+
+    Some(FontInfo {
+        name,
+        path,
+        weight,
+        italic,
+        stretch,
+        features,
+        axes,
+    })
+}
\ No newline at end of file
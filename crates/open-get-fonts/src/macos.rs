@@ -1,38 +1,208 @@
-use super::FontInfo;
+use super::{opentype, select_best_face, FontAxis, FontInfo, MatchRequest};
 use core_foundation::array::{CFArray, CFArrayRef};
 use core_foundation::base::{CFType, TCFType};
+use core_foundation::data::{CFData, CFDataRef};
 use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::number::CFNumber;
 use core_foundation::string::{CFString, CFStringRef};
 use core_foundation::url::{CFURL, CFURLRef};
-use core_text::font::CTFont;
+use core_text::font::{CTFont, CTFontRef};
 use core_text::font_collection::CTFontCollection;
-use core_text::font_descriptor::{CTFontDescriptor, CTFontDescriptorRef};
+use core_text::font_descriptor::{
+    kCTFontTraitsAttribute, CTFontDescriptor, CTFontDescriptorRef,
+};
+use core_text::font_trait_manager::{
+    kCTFontItalicTrait, kCTFontSymbolicTrait, kCTFontWeightTrait, kCTFontWidthTrait,
+};
 use std::path::PathBuf;
 
+// Not all of these are exposed by the core_text crate, so pull them
+// straight from the CoreText framework like core_text itself does.
+extern "C" {
+    static kCTFontVariationAxesAttribute: CFStringRef;
+    static kCTFontVariationAxisIdentifierKey: CFStringRef;
+    static kCTFontVariationAxisMinimumValueKey: CFStringRef;
+    static kCTFontVariationAxisMaximumValueKey: CFStringRef;
+    static kCTFontVariationAxisDefaultValueKey: CFStringRef;
+
+    fn CTFontCopyTable(font: CTFontRef, table: u32, options: u32) -> CFDataRef;
+}
+
+// Packs a four-character table tag the way CTFontCopyTable expects: a
+// big-endian FourCharCode, bytes in their natural left-to-right order.
+fn ct_table_tag(tag: &str) -> u32 {
+    let bytes = tag.as_bytes();
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+// CoreText doesn't expose a documented attribute for a face's supported
+// OpenType feature tags, so read the GSUB/GPOS tables straight off the
+// resolved CTFont via CTFontCopyTable. Reading the table off the CTFont
+// itself (rather than the font file on disk) always matches the exact face
+// CTFontCollection enumerated, including faces other than index 0 inside a
+// TrueType collection.
+unsafe fn read_features(font: &CTFont) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    for tag in ["GSUB", "GPOS"] {
+        let data = CTFontCopyTable(font.as_concrete_TypeRef(), ct_table_tag(tag), 0);
+        if data.is_null() {
+            continue;
+        }
+        let data = CFData::wrap_under_create_rule(data);
+        tags.extend(opentype::parse_feature_tags(data.bytes()));
+    }
+
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Reads variable-font axes out of kCTFontVariationAxesAttribute's array of
+// axis dictionaries. The axis identifier is a packed four-char OSType code.
+unsafe fn read_axes(descriptor: &CTFontDescriptor) -> Vec<FontAxis> {
+    let key = CFString::wrap_under_get_rule(kCTFontVariationAxesAttribute);
+
+    let raw_axes = match descriptor.get_object(&key) {
+        Some(axes) => CFArray::<CFType>::wrap_under_get_rule(axes.as_CFTypeRef() as CFArrayRef),
+        None => return Vec::new(),
+    };
+
+    let id_key = CFString::wrap_under_get_rule(kCTFontVariationAxisIdentifierKey);
+    let min_key = CFString::wrap_under_get_rule(kCTFontVariationAxisMinimumValueKey);
+    let max_key = CFString::wrap_under_get_rule(kCTFontVariationAxisMaximumValueKey);
+    let default_key = CFString::wrap_under_get_rule(kCTFontVariationAxisDefaultValueKey);
+
+    let mut axes = Vec::new();
+    for raw_axis in raw_axes.iter() {
+        let raw_axis: CFDictionary<CFString, CFType> =
+            CFDictionary::wrap_under_get_rule(raw_axis.as_CFTypeRef() as CFDictionaryRef);
+
+        let tag = match raw_axis
+            .find(&id_key)
+            .and_then(|v| v.downcast::<CFNumber>())
+            .and_then(|n| n.to_i64())
+        {
+            Some(id) => String::from_utf8_lossy(&(id as u32).to_be_bytes()).to_string(),
+            None => continue,
+        };
+
+        let min = raw_axis
+            .find(&min_key)
+            .and_then(|v| v.downcast::<CFNumber>())
+            .and_then(|n| n.to_f64())
+            .unwrap_or(0.0) as f32;
+        let max = raw_axis
+            .find(&max_key)
+            .and_then(|v| v.downcast::<CFNumber>())
+            .and_then(|n| n.to_f64())
+            .unwrap_or(0.0) as f32;
+        let default = raw_axis
+            .find(&default_key)
+            .and_then(|v| v.downcast::<CFNumber>())
+            .and_then(|n| n.to_f64())
+            .unwrap_or(0.0) as f32;
+
+        axes.push(FontAxis { tag, min, max, default });
+    }
+
+    axes
+}
+
+// Core Text reports weight/width as normalized traits (-1.0..1.0, 0.0 being
+// regular/normal). Convert those to the same scales the rest of the crate
+// uses: CSS-style 100-900 weight and font-kit's 0.5-2.0 stretch factor.
+fn weight_from_trait(value: f64) -> u16 {
+    if value >= 0.0 {
+        (400.0 + value * 500.0) as u16
+    } else {
+        (400.0 + value * 300.0) as u16
+    }
+}
+
+fn stretch_from_trait(value: f64) -> f32 {
+    (1.0 + value as f32).max(0.5).min(2.0)
+}
+
 pub fn get_core_text_fonts() -> Option<Vec<FontInfo>> {
     let collection = CTFontCollection::create_for_all_families();
     let descriptors = collection.get_descriptors()?;
     let mut fonts = Vec::new();
-    
+
     for i in 0..descriptors.len() {
         let descriptor = descriptors.get(i);
         let font = CTFont::new_from_descriptor(&descriptor, 0.0);
-        
+
         // Get font name
         let name = font.family_name();
-        
+
+        // Read weight/width/italic out of the traits dictionary
+        let (weight, italic, stretch) = unsafe {
+            let traits_key = CFString::wrap_under_get_rule(kCTFontTraitsAttribute);
+            if let Some(traits) = descriptor.get_object(&traits_key) {
+                let traits: CFDictionary<CFString, CFType> =
+                    CFDictionary::wrap_under_get_rule(traits.as_CFTypeRef() as CFDictionaryRef);
+
+                let weight_key = CFString::wrap_under_get_rule(kCTFontWeightTrait);
+                let weight = traits
+                    .find(&weight_key)
+                    .and_then(|v| v.downcast::<CFNumber>())
+                    .and_then(|n| n.to_f64())
+                    .map(weight_from_trait)
+                    .unwrap_or(400);
+
+                let width_key = CFString::wrap_under_get_rule(kCTFontWidthTrait);
+                let stretch = traits
+                    .find(&width_key)
+                    .and_then(|v| v.downcast::<CFNumber>())
+                    .and_then(|n| n.to_f64())
+                    .map(stretch_from_trait)
+                    .unwrap_or(1.0);
+
+                let symbolic_key = CFString::wrap_under_get_rule(kCTFontSymbolicTrait);
+                let italic = traits
+                    .find(&symbolic_key)
+                    .and_then(|v| v.downcast::<CFNumber>())
+                    .and_then(|n| n.to_i64())
+                    .map(|symbolic| (symbolic as u32) & kCTFontItalicTrait != 0)
+                    .unwrap_or(false);
+
+                (weight, italic, stretch)
+            } else {
+                (400, false, 1.0)
+            }
+        };
+
         // Get font path
         let url_key = unsafe { CFString::wrap_under_get_rule(CTFontDescriptor::get_url_attribute_key()) };
-        
+
         if let Some(url) = descriptor.get_url(&url_key) {
             if let Some(path) = url.to_path() {
+                let (features, axes) = unsafe { (read_features(&font), read_axes(&descriptor)) };
                 fonts.push(FontInfo {
                     name: name.to_string(),
                     path: path.to_string_lossy().to_string(),
+                    weight,
+                    italic,
+                    stretch,
+                    features,
+                    axes,
                 });
             }
         }
     }
-    
+
     Some(fonts)
-} 
\ No newline at end of file
+}
+
+// Native fallback for matchFont: gather every face Core Text reports for
+// this family, then apply the classic CSS nearest-weight tie-break and a
+// preference for the requested slant before falling back to upright.
+pub fn match_core_text_font(family: &str, request: MatchRequest) -> Option<FontInfo> {
+    let candidates: Vec<FontInfo> = get_core_text_fonts()?
+        .into_iter()
+        .filter(|font| font.name == family)
+        .collect();
+
+    select_best_face(&candidates, request)
+}
\ No newline at end of file
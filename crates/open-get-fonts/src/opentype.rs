@@ -0,0 +1,134 @@
+// Minimal, dependency-free reader for the handful of OpenType tables we
+// need: `fvar` for variable-font axes, and the feature lists inside
+// `GSUB`/`GPOS` for supported OpenType feature tags. Font-kit already hands
+// us the raw font bytes (or the native backends hand us raw table bytes),
+// so there's no need to pull in a full font-parsing crate just for this.
+
+use crate::FontAxis;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    read_u32(data, offset).map(|v| v as i32)
+}
+
+fn tag_at(data: &[u8], offset: usize) -> Option<String> {
+    data.get(offset..offset + 4)
+        .map(|b| String::from_utf8_lossy(b).trim_end().to_string())
+}
+
+// Finds an sfnt table's (offset, length) within the font file, following
+// the TrueType Collection header to the requested face first if present.
+fn find_table(data: &[u8], face_index: u32, tag: &str) -> Option<(usize, usize)> {
+    let sfnt_offset = if data.get(0..4) == Some(b"ttcf") {
+        read_u32(data, 12 + face_index as usize * 4)? as usize
+    } else {
+        0
+    };
+
+    let num_tables = read_u16(data, sfnt_offset + 4)? as usize;
+    let records_start = sfnt_offset + 12;
+
+    for i in 0..num_tables {
+        let record = records_start + i * 16;
+        if tag_at(data, record)?.as_str() == tag {
+            let table_offset = read_u32(data, record + 8)? as usize;
+            let table_length = read_u32(data, record + 12)? as usize;
+            return Some((table_offset, table_length));
+        }
+    }
+
+    None
+}
+
+// Parses an `fvar` table (already sliced to just that table's bytes) into
+// our FontAxis list. Axis values are 16.16 fixed-point per the spec.
+pub fn parse_fvar_axes(table: &[u8]) -> Vec<FontAxis> {
+    let mut axes = Vec::new();
+
+    let axes_array_offset = match read_u16(table, 4) {
+        Some(v) => v as usize,
+        None => return axes,
+    };
+    let axis_count = match read_u16(table, 8) {
+        Some(v) => v as usize,
+        None => return axes,
+    };
+    let axis_size = match read_u16(table, 10) {
+        Some(v) => v as usize,
+        None => return axes,
+    };
+
+    for i in 0..axis_count {
+        let record = axes_array_offset + i * axis_size;
+        let tag = match tag_at(table, record) {
+            Some(t) => t,
+            None => continue,
+        };
+        let min = read_i32(table, record + 4).unwrap_or(0) as f32 / 65536.0;
+        let default = read_i32(table, record + 8).unwrap_or(0) as f32 / 65536.0;
+        let max = read_i32(table, record + 12).unwrap_or(0) as f32 / 65536.0;
+
+        axes.push(FontAxis { tag, min, max, default });
+    }
+
+    axes
+}
+
+// Parses the FeatureList of a GSUB/GPOS table (already sliced to just that
+// table's bytes) into its feature tags.
+pub fn parse_feature_tags(table: &[u8]) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let feature_list_offset = match read_u16(table, 6) {
+        Some(v) => v as usize,
+        None => return tags,
+    };
+    let feature_count = match read_u16(table, feature_list_offset) {
+        Some(v) => v as usize,
+        None => return tags,
+    };
+
+    for i in 0..feature_count {
+        let record = feature_list_offset + 2 + i * 6;
+        if let Some(tag) = tag_at(table, record) {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+// Reads every variation axis out of a whole font file's `fvar` table.
+pub fn read_variation_axes(font_data: &[u8], face_index: u32) -> Vec<FontAxis> {
+    find_table(font_data, face_index, "fvar")
+        .and_then(|(offset, length)| font_data.get(offset..offset + length))
+        .map(parse_fvar_axes)
+        .unwrap_or_default()
+}
+
+// Reads every OpenType feature tag out of a whole font file's GSUB/GPOS
+// tables.
+pub fn read_features(font_data: &[u8], face_index: u32) -> Vec<String> {
+    let mut features = Vec::new();
+
+    for tag in ["GSUB", "GPOS"] {
+        if let Some(table) = find_table(font_data, face_index, tag)
+            .and_then(|(offset, length)| font_data.get(offset..offset + length))
+        {
+            features.extend(parse_feature_tags(table));
+        }
+    }
+
+    features.sort();
+    features.dedup();
+    features
+}
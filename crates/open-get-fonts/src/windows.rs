@@ -1,16 +1,237 @@
-use super::FontInfo;
-use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
-use std::path::PathBuf;
+use super::{opentype, FontInfo, MatchRequest};
 use std::ptr;
 use winapi::shared::minwindef::{DWORD, FALSE};
 use winapi::shared::winerror::SUCCEEDED;
 use winapi::um::dwrite::{
-    DWriteCreateFactory, IDWriteFactory, IDWriteFontCollection, IDWriteFontFamily,
-    IDWriteLocalizedStrings, DWRITE_FACTORY_TYPE_SHARED, DWRITE_FONT_FAMILY_MODEL_TYPOGRAPHIC,
+    DWriteCreateFactory, IDWriteFactory, IDWriteFont, IDWriteFontCollection, IDWriteFontFace,
+    IDWriteFontFamily, IDWriteFontFile, IDWriteFontFileLoader, IDWriteFontFileStream,
+    IDWriteLocalFontFileLoader, IDWriteLocalizedStrings, DWRITE_FACTORY_TYPE_SHARED,
+    DWRITE_FONT_FAMILY_MODEL_TYPOGRAPHIC, DWRITE_FONT_STRETCH, DWRITE_FONT_STYLE,
+    DWRITE_FONT_STYLE_NORMAL,
 };
 use widestring::U16CString;
 
+// DirectWrite's DWRITE_FONT_STRETCH is a 1-9 enum (ultra-condensed to
+// ultra-expanded); map it onto font-kit's 0.5-2.0 stretch factor so the
+// value means the same thing across platforms.
+fn stretch_from_dwrite(stretch: DWRITE_FONT_STRETCH) -> f32 {
+    match stretch {
+        1 => 0.5,
+        2 => 0.625,
+        3 => 0.75,
+        4 => 0.875,
+        6 => 1.125,
+        7 => 1.25,
+        8 => 1.5,
+        9 => 2.0,
+        _ => 1.0,
+    }
+}
+
+fn italic_from_dwrite(style: DWRITE_FONT_STYLE) -> bool {
+    style != DWRITE_FONT_STYLE_NORMAL
+}
+
+// Packs a four-character table tag the way DWRITE_MAKE_OPENTYPE_TAG does:
+// little-endian, first character in the low byte.
+fn opentype_tag(tag: &str) -> u32 {
+    let bytes = tag.as_bytes();
+    (bytes[0] as u32)
+        | (bytes[1] as u32) << 8
+        | (bytes[2] as u32) << 16
+        | (bytes[3] as u32) << 24
+}
+
+// Reads OpenType feature tags and variation axes straight out of the
+// face's GSUB/GPOS/fvar tables via TryGetFontTable, reusing the same
+// table parsers the font-kit path uses on the raw font bytes.
+unsafe fn read_face_features_and_axes(face: *mut IDWriteFontFace) -> (Vec<String>, Vec<super::FontAxis>) {
+    let mut features = Vec::new();
+    for tag in ["GSUB", "GPOS"] {
+        let mut table_data: *const std::ffi::c_void = ptr::null();
+        let mut table_size: u32 = 0;
+        let mut table_context: *mut std::ffi::c_void = ptr::null_mut();
+        let mut exists: i32 = FALSE;
+
+        let hr = (*face).TryGetFontTable(
+            opentype_tag(tag),
+            &mut table_data,
+            &mut table_size,
+            &mut table_context,
+            &mut exists,
+        );
+
+        if SUCCEEDED(hr) && exists != FALSE {
+            let table =
+                std::slice::from_raw_parts(table_data as *const u8, table_size as usize);
+            features.extend(opentype::parse_feature_tags(table));
+            (*face).ReleaseFontTable(table_context);
+        }
+    }
+    features.sort();
+    features.dedup();
+
+    let mut axes = Vec::new();
+    let mut table_data: *const std::ffi::c_void = ptr::null();
+    let mut table_size: u32 = 0;
+    let mut table_context: *mut std::ffi::c_void = ptr::null_mut();
+    let mut exists: i32 = FALSE;
+
+    let hr = (*face).TryGetFontTable(
+        opentype_tag("fvar"),
+        &mut table_data,
+        &mut table_size,
+        &mut table_context,
+        &mut exists,
+    );
+
+    if SUCCEEDED(hr) && exists != FALSE {
+        let table = std::slice::from_raw_parts(table_data as *const u8, table_size as usize);
+        axes = opentype::parse_fvar_axes(table);
+        (*face).ReleaseFontTable(table_context);
+    }
+
+    (features, axes)
+}
+
+// Inverse of stretch_from_dwrite: map our 0.5-2.0 stretch factor back onto
+// DirectWrite's 1-9 enum for use as a matching request.
+fn dwrite_stretch_from_factor(stretch: f32) -> DWRITE_FONT_STRETCH {
+    if stretch <= 0.5 {
+        1
+    } else if stretch <= 0.625 {
+        2
+    } else if stretch <= 0.75 {
+        3
+    } else if stretch <= 0.875 {
+        4
+    } else if stretch <= 1.0 {
+        5
+    } else if stretch <= 1.125 {
+        6
+    } else if stretch <= 1.25 {
+        7
+    } else if stretch <= 1.5 {
+        8
+    } else {
+        9
+    }
+}
+
+// Reads a name out of an IDWriteLocalizedStrings, preferring "en-us" the way
+// Gecko's DWrite backend does so the result is stable and matches what users
+// see in other tools, instead of depending on whatever locale happens to be
+// first in the collection.
+unsafe fn read_localized_name(names: *mut IDWriteLocalizedStrings) -> Option<String> {
+    let locale = U16CString::from_str("en-us").ok()?;
+    let mut index: u32 = 0;
+    let mut exists: i32 = FALSE;
+    let hr = (*names).FindLocaleName(locale.as_ptr(), &mut index, &mut exists);
+
+    if !SUCCEEDED(hr) || exists == FALSE {
+        index = 0;
+    }
+
+    let mut length: u32 = 0;
+    if !SUCCEEDED((*names).GetStringLength(index, &mut length)) {
+        return None;
+    }
+
+    // +1 for null terminator
+    let mut buffer: Vec<u16> = vec![0; (length + 1) as usize];
+    if !SUCCEEDED((*names).GetString(index, buffer.as_mut_ptr(), length + 1)) {
+        return None;
+    }
+
+    U16CString::from_vec_with_nul(buffer)
+        .ok()
+        .and_then(|s| s.to_string().ok())
+}
+
+// Resolves the on-disk path(s) of a font face the way Gecko's DWrite backend
+// does: walk the face's IDWriteFontFile list and, for each one backed by the
+// local file loader, recover the real path via GetFilePathFromKey. Files
+// backed by a non-local loader (memory/streamed fonts) are skipped and
+// reported with an empty path, mirroring the Handle::Memory branch in
+// font_info_from_handle.
+unsafe fn resolve_face_path(face: *mut IDWriteFontFace) -> String {
+    let mut file_count: u32 = 0;
+    if !SUCCEEDED((*face).GetFiles(&mut file_count, ptr::null_mut())) || file_count == 0 {
+        return String::new();
+    }
+
+    let mut files: Vec<*mut IDWriteFontFile> = vec![ptr::null_mut(); file_count as usize];
+    if !SUCCEEDED((*face).GetFiles(&mut file_count, files.as_mut_ptr())) {
+        return String::new();
+    }
+
+    for file in files {
+        if file.is_null() {
+            continue;
+        }
+
+        let mut key_ptr: *const std::ffi::c_void = ptr::null();
+        let mut key_size: u32 = 0;
+        let mut loader: *mut IDWriteFontFileLoader = ptr::null_mut();
+
+        if !SUCCEEDED((*file).GetReferenceKey(&mut key_ptr, &mut key_size))
+            || !SUCCEEDED((*file).GetLoader(&mut loader))
+        {
+            (*file).Release();
+            continue;
+        }
+
+        let mut local_loader: *mut IDWriteLocalFontFileLoader = ptr::null_mut();
+        let hr = (*loader).QueryInterface(
+            &IDWriteLocalFontFileLoader::uuidof(),
+            &mut local_loader as *mut _ as *mut _,
+        );
+
+        if !SUCCEEDED(hr) || local_loader.is_null() {
+            // Not backed by a local file (e.g. memory or streamed font).
+            (*loader).Release();
+            (*file).Release();
+            continue;
+        }
+
+        let mut path_length: u32 = 0;
+        let hr = (*local_loader).GetFilePathLengthFromKey(key_ptr, key_size, &mut path_length);
+
+        if !SUCCEEDED(hr) {
+            (*local_loader).Release();
+            (*loader).Release();
+            (*file).Release();
+            continue;
+        }
+
+        // +1 for null terminator
+        let mut path_buffer: Vec<u16> = vec![0; (path_length + 1) as usize];
+        let hr = (*local_loader).GetFilePathFromKey(
+            key_ptr,
+            key_size,
+            path_buffer.as_mut_ptr(),
+            path_length + 1,
+        );
+
+        (*local_loader).Release();
+        (*loader).Release();
+        (*file).Release();
+
+        if !SUCCEEDED(hr) {
+            continue;
+        }
+
+        if let Some(path) = U16CString::from_vec_with_nul(path_buffer)
+            .ok()
+            .and_then(|s| s.to_string().ok())
+        {
+            return path;
+        }
+    }
+
+    String::new()
+}
+
 pub fn get_directwrite_fonts() -> Option<Vec<FontInfo>> {
     unsafe {
         let mut factory: *mut IDWriteFactory = ptr::null_mut();
@@ -19,69 +240,322 @@ pub fn get_directwrite_fonts() -> Option<Vec<FontInfo>> {
             &IDWriteFactory::uuidof(),
             &mut factory as *mut _ as *mut _,
         );
-        
+
         if !SUCCEEDED(hr) {
             return None;
         }
-        
+
         let mut font_collection: *mut IDWriteFontCollection = ptr::null_mut();
         let hr = (*factory).GetSystemFontCollection(&mut font_collection, FALSE);
-        
+
         if !SUCCEEDED(hr) {
             return None;
         }
-        
+
         let mut fonts = Vec::new();
         let family_count = (*font_collection).GetFontFamilyCount();
-        
+
         for i in 0..family_count {
             let mut family: *mut IDWriteFontFamily = ptr::null_mut();
             let hr = (*font_collection).GetFontFamily(i, &mut family);
-            
+
             if !SUCCEEDED(hr) {
                 continue;
             }
-            
+
             let mut names: *mut IDWriteLocalizedStrings = ptr::null_mut();
             let hr = (*family).GetFamilyNames(&mut names);
-            
-            if !SUCCEEDED(hr) {
-                continue;
-            }
-            
-            // Get family name
-            let mut name_length: u32 = 0;
-            let hr = (*names).GetStringLength(0, &mut name_length);
-            
+
             if !SUCCEEDED(hr) {
+                (*family).Release();
                 continue;
             }
-            
-            // +1 for null terminator
-            let mut name_buffer: Vec<u16> = vec![0; (name_length + 1) as usize];
-            let hr = (*names).GetString(0, name_buffer.as_mut_ptr(), name_length + 1);
-            
-            if !SUCCEEDED(hr) {
-                continue;
+
+            let name = read_localized_name(names);
+            (*names).Release();
+
+            let name = match name {
+                Some(name) => name,
+                None => {
+                    (*family).Release();
+                    continue;
+                }
+            };
+
+            // Enumerate every face in the family (regular, bold, italic,
+            // condensed, ...) instead of just the first one.
+            let font_count = (*family).GetFontCount();
+
+            for j in 0..font_count {
+                let mut font: *mut IDWriteFont = ptr::null_mut();
+                let hr = (*family).GetFont(j, &mut font);
+
+                if !SUCCEEDED(hr) {
+                    continue;
+                }
+
+                let weight = (*font).GetWeight() as u16;
+                let italic = italic_from_dwrite((*font).GetStyle());
+                let stretch = stretch_from_dwrite((*font).GetStretch());
+
+                let mut face: *mut IDWriteFontFace = ptr::null_mut();
+                let hr = (*font).CreateFontFace(&mut face);
+
+                if !SUCCEEDED(hr) {
+                    (*font).Release();
+                    continue;
+                }
+
+                let path = resolve_face_path(face);
+                let (features, axes) = read_face_features_and_axes(face);
+
+                (*face).Release();
+                (*font).Release();
+
+                fonts.push(FontInfo {
+                    name: name.clone(),
+                    path,
+                    weight,
+                    italic,
+                    stretch,
+                    features,
+                    axes,
+                });
             }
-            
-            // Convert to Rust string
-            let name = U16CString::from_vec_with_nul(name_buffer)
-                .ok()
-                .and_then(|s| s.to_string().ok())
-                .unwrap_or_default();
-            
-            // Get font path (in Windows, this is more complex and generally requires 
-            // looking up in the registry or enumerating font files directly)
-            // For simplicity, we'll just use a placeholder path pattern
-            let path = format!("C:\\Windows\\Fonts\\{}.ttf", name);
-            
-            fonts.push(FontInfo {
-                name,
-                path,
-            });
-        }
-        
+
+            (*family).Release();
+        }
+
         Some(fonts)
     }
-} 
\ No newline at end of file
+}
+
+// Native fallback for matchFont: look the family up in the system
+// collection by name, then let DirectWrite's own GetFirstMatchingFont
+// resolve the nearest weight/stretch/style.
+pub fn match_directwrite_font(family: &str, request: MatchRequest) -> Option<FontInfo> {
+    unsafe {
+        let mut factory: *mut IDWriteFactory = ptr::null_mut();
+        let hr = DWriteCreateFactory(
+            DWRITE_FACTORY_TYPE_SHARED,
+            &IDWriteFactory::uuidof(),
+            &mut factory as *mut _ as *mut _,
+        );
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let mut font_collection: *mut IDWriteFontCollection = ptr::null_mut();
+        let hr = (*factory).GetSystemFontCollection(&mut font_collection, FALSE);
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let family_name = U16CString::from_str(family).ok()?;
+        let mut index: u32 = 0;
+        let mut exists: i32 = FALSE;
+        let hr = (*font_collection).FindFamilyName(family_name.as_ptr(), &mut index, &mut exists);
+
+        if !SUCCEEDED(hr) || exists == FALSE {
+            return None;
+        }
+
+        let mut font_family: *mut IDWriteFontFamily = ptr::null_mut();
+        let hr = (*font_collection).GetFontFamily(index, &mut font_family);
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let style = if request.italic {
+            winapi::um::dwrite::DWRITE_FONT_STYLE_ITALIC
+        } else {
+            DWRITE_FONT_STYLE_NORMAL
+        };
+
+        let mut font: *mut IDWriteFont = ptr::null_mut();
+        let hr = (*font_family).GetFirstMatchingFont(
+            request.weight as u32,
+            dwrite_stretch_from_factor(request.stretch),
+            style,
+            &mut font,
+        );
+
+        (*font_family).Release();
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let weight = (*font).GetWeight() as u16;
+        let italic = italic_from_dwrite((*font).GetStyle());
+        let stretch = stretch_from_dwrite((*font).GetStretch());
+
+        let mut face: *mut IDWriteFontFace = ptr::null_mut();
+        let hr = (*font).CreateFontFace(&mut face);
+
+        if !SUCCEEDED(hr) {
+            (*font).Release();
+            return None;
+        }
+
+        let path = resolve_face_path(face);
+        let (features, axes) = read_face_features_and_axes(face);
+
+        (*face).Release();
+        (*font).Release();
+
+        Some(FontInfo {
+            name: family.to_string(),
+            path,
+            weight,
+            italic,
+            stretch,
+            features,
+            axes,
+        })
+    }
+}
+
+// Native fallback for getFontData: reads the font file bytes straight out
+// of its IDWriteFontFileStream. This also reaches fonts that report no
+// local path (memory/streamed fonts, which resolve_face_path skips); for a
+// face inside a TrueType collection it returns the whole collection file
+// along with the face's index.
+pub fn font_data_for_family(family: &str) -> Option<(Vec<u8>, u32)> {
+    unsafe {
+        let mut factory: *mut IDWriteFactory = ptr::null_mut();
+        let hr = DWriteCreateFactory(
+            DWRITE_FACTORY_TYPE_SHARED,
+            &IDWriteFactory::uuidof(),
+            &mut factory as *mut _ as *mut _,
+        );
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let mut font_collection: *mut IDWriteFontCollection = ptr::null_mut();
+        let hr = (*factory).GetSystemFontCollection(&mut font_collection, FALSE);
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let family_name = U16CString::from_str(family).ok()?;
+        let mut index: u32 = 0;
+        let mut exists: i32 = FALSE;
+        let hr = (*font_collection).FindFamilyName(family_name.as_ptr(), &mut index, &mut exists);
+
+        if !SUCCEEDED(hr) || exists == FALSE {
+            return None;
+        }
+
+        let mut font_family: *mut IDWriteFontFamily = ptr::null_mut();
+        let hr = (*font_collection).GetFontFamily(index, &mut font_family);
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let mut font: *mut IDWriteFont = ptr::null_mut();
+        let hr = (*font_family).GetFont(0, &mut font);
+
+        (*font_family).Release();
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let mut face: *mut IDWriteFontFace = ptr::null_mut();
+        let hr = (*font).CreateFontFace(&mut face);
+
+        (*font).Release();
+
+        if !SUCCEEDED(hr) {
+            return None;
+        }
+
+        let face_index = (*face).GetIndex();
+
+        let mut file_count: u32 = 0;
+        if !SUCCEEDED((*face).GetFiles(&mut file_count, ptr::null_mut())) || file_count == 0 {
+            (*face).Release();
+            return None;
+        }
+
+        let mut files: Vec<*mut IDWriteFontFile> = vec![ptr::null_mut(); file_count as usize];
+        if !SUCCEEDED((*face).GetFiles(&mut file_count, files.as_mut_ptr())) {
+            (*face).Release();
+            return None;
+        }
+
+        let file = match files.get(0) {
+            Some(file) => *file,
+            None => {
+                (*face).Release();
+                return None;
+            }
+        };
+        if file.is_null() {
+            (*face).Release();
+            return None;
+        }
+
+        let mut key_ptr: *const std::ffi::c_void = ptr::null();
+        let mut key_size: u32 = 0;
+        let mut loader: *mut IDWriteFontFileLoader = ptr::null_mut();
+
+        if !SUCCEEDED((*file).GetReferenceKey(&mut key_ptr, &mut key_size))
+            || !SUCCEEDED((*file).GetLoader(&mut loader))
+        {
+            (*file).Release();
+            (*face).Release();
+            return None;
+        }
+
+        let mut stream: *mut IDWriteFontFileStream = ptr::null_mut();
+        let hr = (*loader).CreateStreamFromKey(key_ptr, key_size, &mut stream);
+
+        (*loader).Release();
+        (*file).Release();
+
+        if !SUCCEEDED(hr) {
+            (*face).Release();
+            return None;
+        }
+
+        let mut file_size: u64 = 0;
+        if !SUCCEEDED((*stream).GetFileSize(&mut file_size)) {
+            (*stream).Release();
+            (*face).Release();
+            return None;
+        }
+
+        let mut fragment_start: *const std::ffi::c_void = ptr::null();
+        let mut fragment_context: *mut std::ffi::c_void = ptr::null_mut();
+        let hr = (*stream).ReadFileFragment(
+            &mut fragment_start,
+            0,
+            file_size,
+            &mut fragment_context,
+        );
+
+        if !SUCCEEDED(hr) {
+            (*stream).Release();
+            (*face).Release();
+            return None;
+        }
+
+        let bytes = std::slice::from_raw_parts(fragment_start as *const u8, file_size as usize)
+            .to_vec();
+
+        (*stream).ReleaseFileFragment(fragment_context);
+        (*stream).Release();
+        (*face).Release();
+
+        Some((bytes, face_index))
+    }
+}